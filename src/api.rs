@@ -0,0 +1,118 @@
+//! Local HTTP control API, started alongside [`crate::profile_applier`] when
+//! enabled in settings. Lets a user inspect and manually drive the service
+//! without locking their screen.
+
+use std::sync::{Arc, Mutex};
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use openrgb::OpenRGB;
+use serde::Serialize;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex as AsyncMutex;
+
+/// The connection/profile state the API reports back, updated by
+/// [`crate::profile_applier`] as it connects, reconnects, and applies
+/// profiles.
+#[derive(Debug, Default)]
+pub(crate) struct StatusState {
+    pub last_profile: Option<String>,
+    pub connected: bool,
+}
+
+#[derive(Clone)]
+pub(crate) struct ApiState {
+    pub profile_send: UnboundedSender<bool>,
+    pub client: Arc<AsyncMutex<Option<OpenRGB<TcpStream>>>>,
+    pub status: Arc<Mutex<StatusState>>,
+}
+
+#[derive(Serialize)]
+struct StatusResponse {
+    last_profile: Option<String>,
+    connected: bool,
+    profiles: Vec<String>,
+    devices: Vec<String>,
+}
+
+/// Best-effort profile list: an empty `Vec` (rather than an error) if the
+/// client isn't currently connected, so `/status` still reports the rest of
+/// the state.
+async fn fetch_profiles(client: &Option<OpenRGB<TcpStream>>) -> Vec<String> {
+    let Some(client) = client else {
+        return Vec::new();
+    };
+
+    client.get_profiles().await.unwrap_or_default()
+}
+
+/// Best-effort device list, by the same reasoning as [`fetch_profiles`].
+async fn fetch_devices(client: &Option<OpenRGB<TcpStream>>) -> Vec<String> {
+    let Some(client) = client else {
+        return Vec::new();
+    };
+
+    let Ok(count) = client.get_controller_count().await else {
+        return Vec::new();
+    };
+
+    let mut devices = Vec::with_capacity(count as usize);
+    for id in 0..count {
+        if let Ok(controller) = client.get_controller_data(id).await {
+            devices.push(controller.name);
+        }
+    }
+    devices
+}
+
+async fn get_status(State(state): State<ApiState>) -> Json<StatusResponse> {
+    let client = state.client.lock().await;
+    let profiles = fetch_profiles(&client).await;
+    let devices = fetch_devices(&client).await;
+    drop(client);
+
+    let status = state.status.lock().unwrap();
+    Json(StatusResponse {
+        last_profile: status.last_profile.clone(),
+        connected: status.connected,
+        profiles,
+        devices,
+    })
+}
+
+async fn post_enable(State(state): State<ApiState>) -> StatusCode {
+    match state.profile_send.send(true) {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+async fn post_disable(State(state): State<ApiState>) -> StatusCode {
+    match state.profile_send.send(false) {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(_) => StatusCode::SERVICE_UNAVAILABLE,
+    }
+}
+
+async fn get_profiles(State(state): State<ApiState>) -> Result<Json<Vec<String>>, StatusCode> {
+    let client = state.client.lock().await;
+    let client = client.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+
+    client
+        .get_profiles()
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::SERVICE_UNAVAILABLE)
+}
+
+pub(crate) fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/status", get(get_status))
+        .route("/enable", post(post_enable))
+        .route("/disable", post(post_disable))
+        .route("/profiles", get(get_profiles))
+        .with_state(state)
+}
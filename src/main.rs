@@ -1,6 +1,7 @@
 use std::ffi::OsString;
+use std::sync::OnceLock;
 
-use log::{debug, info};
+use log::{debug, info, warn};
 use log4rs::Handle;
 use openrgb::OpenRGB;
 use windows_service::{
@@ -12,14 +13,32 @@ use windows_service::{
 use std::time::Duration;
 use tokio::{net::TcpStream, runtime::Runtime, sync::mpsc};
 
-use tokio::sync::mpsc::UnboundedReceiver;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio_util::sync::CancellationToken;
 use windows_service::{define_windows_service, service_dispatcher};
 
+mod api;
+mod settings;
+mod temperature;
+mod user_mode;
+
+use settings::{Mode, Settings};
+
 const SERVICE_NAME: &str = "RGBXevion";
 const SERVICE_DESCRIPTION: &str = "Custom service to toggle RGB lights based on lock/sleep events";
 
-const PROFILE_ENABLE_NAME: &str = "On";
-const PROFILE_DISABLE_NAME: &str = "Off";
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Loads settings on first access (writing out defaults if no config file
+/// exists yet) and caches the result for the lifetime of the process.
+pub(crate) fn settings() -> &'static Settings {
+    SETTINGS.get_or_init(|| {
+        Settings::load().unwrap_or_else(|err| {
+            log::error!("Failed to load settings, falling back to defaults: {}", err);
+            Settings::default()
+        })
+    })
+}
 
 define_windows_service!(ffi_service_main, service_main);
 
@@ -41,28 +60,229 @@ pub async fn try_load_profile(
     Ok(())
 }
 
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Connects to the configured OpenRGB server, retrying with exponential
+/// backoff (capped at [`RECONNECT_MAX_BACKOFF`]) until it succeeds or
+/// `shutdown` is cancelled.
+async fn connect_with_backoff(
+    settings: &Settings,
+    shutdown: &CancellationToken,
+) -> Option<OpenRGB<TcpStream>> {
+    let mut backoff = RECONNECT_INITIAL_BACKOFF;
+
+    loop {
+        match OpenRGB::connect_to((settings.openrgb.host.as_str(), settings.openrgb.port)).await {
+            Ok(client) => return Some(client),
+            Err(err) => {
+                warn!(
+                    "Failed to connect to OpenRGB at {}:{} ({}), retrying in {:?}",
+                    settings.openrgb.host, settings.openrgb.port, err, backoff
+                );
+
+                tokio::select! {
+                    _ = tokio::time::sleep(backoff) => {}
+                    _ = shutdown.cancelled() => return None,
+                }
+
+                backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+async fn apply_profile(client: &OpenRGB<TcpStream>, settings: &Settings, enable: bool) -> anyhow::Result<()> {
+    let profile_name = if enable {
+        &settings.profile_enable_name
+    } else {
+        &settings.profile_disable_name
+    };
+
+    try_load_profile(client, profile_name).await
+}
+
+/// Sets every LED of the configured devices (or all devices, if none are
+/// configured) to `color` via direct device updates.
+async fn apply_temperature_color(
+    client: &OpenRGB<TcpStream>,
+    device_ids: &[u32],
+    color: (u8, u8, u8),
+) -> anyhow::Result<()> {
+    use openrgb::data::Color;
+
+    let ids: Vec<u32> = if device_ids.is_empty() {
+        (0..client.get_controller_count().await?).collect()
+    } else {
+        device_ids.to_vec()
+    };
+
+    let rgb = Color::new(color.0, color.1, color.2);
+    for id in ids {
+        let controller = client.get_controller_data(id).await?;
+        client.update_leds(id, vec![rgb; controller.leds.len()]).await?;
+    }
+
+    Ok(())
+}
+
 pub(crate) async fn profile_applier(
     profile_recv: &mut UnboundedReceiver<bool>,
-    shutdown_recv: &mut UnboundedReceiver<()>,
+    profile_send: UnboundedSender<bool>,
+    shutdown: CancellationToken,
+    settings: &Settings,
 ) -> anyhow::Result<()> {
-    let client = OpenRGB::connect().await?;
-    client
-        .set_name(format!("{} v{}", SERVICE_NAME, env!("CARGO_PKG_VERSION")))
-        .await?;
+    // The most recently requested profile state, reapplied after every
+    // reconnect so a transport hiccup doesn't leave a stale profile active.
+    let mut last_state: Option<bool> = None;
+
+    let shared_client: std::sync::Arc<tokio::sync::Mutex<Option<OpenRGB<TcpStream>>>> =
+        std::sync::Arc::new(tokio::sync::Mutex::new(None));
+    let status = std::sync::Arc::new(std::sync::Mutex::new(api::StatusState::default()));
+
+    type ServerFuture = std::pin::Pin<Box<dyn std::future::Future<Output = std::io::Result<()>> + Send>>;
+
+    let mut server: Option<ServerFuture> = if settings.api.enabled {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", settings.api.port)).await?;
+        info!("Control API listening on http://127.0.0.1:{}", settings.api.port);
+
+        let state = api::ApiState {
+            profile_send,
+            client: shared_client.clone(),
+            status: status.clone(),
+        };
+        Some(Box::pin(async move { axum::serve(listener, api::router(state)).await }))
+    } else {
+        None
+    };
 
-    loop {
-        tokio::select! {
-            enable = profile_recv.recv() => {
-                debug!("Received profile command: {:?}", enable);
-                if enable.is_none() {
-                    continue;
-                }
+    let mut temperature_source = if settings.mode == Mode::Temperature {
+        match temperature::TemperatureSource::new() {
+            Ok(source) => Some(source),
+            Err(err) => {
+                warn!("Failed to initialize temperature source: {}", err);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let mut last_temp_color: Option<(u8, u8, u8)> = None;
+    let mut temp_interval =
+        tokio::time::interval(Duration::from_secs(settings.temperature.poll_interval_secs.max(1)));
+
+    'reconnect: loop {
+        let Some(client) = connect_with_backoff(settings, &shutdown).await else {
+            info!("Service shutting down");
+            return Ok(());
+        };
+
+        if let Err(err) = client
+            .set_name(format!("{} v{}", SERVICE_NAME, env!("CARGO_PKG_VERSION")))
+            .await
+        {
+            warn!("Lost connection to OpenRGB while naming client: {}", err);
+            continue 'reconnect;
+        }
 
-                try_load_profile(&client, if enable.unwrap() { PROFILE_ENABLE_NAME } else { PROFILE_DISABLE_NAME }).await?;
+        *shared_client.lock().await = Some(client);
+        status.lock().unwrap().connected = true;
+        // Force the next temperature tick to reapply, in case the device
+        // dropped the color while we were disconnected.
+        last_temp_color = None;
+
+        if let Some(enable) = last_state {
+            let client_guard = shared_client.lock().await;
+            let client = client_guard.as_ref().unwrap();
+            if apply_profile(client, settings, enable).await.is_err() {
+                drop(client_guard);
+                status.lock().unwrap().connected = false;
+                warn!("Lost connection to OpenRGB while reapplying last state");
+                continue 'reconnect;
             }
-            _ = shutdown_recv.recv() => {
-                info!("Service shutting down");
-                return Ok(())
+        }
+
+        loop {
+            let server_result = async {
+                match server.as_mut() {
+                    Some(server) => server.await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                enable = profile_recv.recv() => {
+                    let Some(mut enable) = enable else { continue; };
+                    // Coalesce any additional commands that arrived while we
+                    // were busy so we only ever apply the latest one.
+                    while let Ok(next) = profile_recv.try_recv() {
+                        enable = next;
+                    }
+
+                    debug!("Received profile command: {:?}", enable);
+                    last_state = Some(enable);
+
+                    let client_guard = shared_client.lock().await;
+                    let client = client_guard.as_ref().unwrap();
+                    let applied = apply_profile(client, settings, enable).await.is_ok();
+                    drop(client_guard);
+
+                    if applied {
+                        status.lock().unwrap().last_profile = Some(if enable {
+                            settings.profile_enable_name.clone()
+                        } else {
+                            settings.profile_disable_name.clone()
+                        });
+                        // The profile load may have overwritten LED state out
+                        // from under the temperature mode's idea of what's
+                        // currently displayed - force the next tick to
+                        // reapply rather than skipping on a stale match.
+                        last_temp_color = None;
+                    } else {
+                        status.lock().unwrap().connected = false;
+                        warn!("Lost connection to OpenRGB, reconnecting");
+                        continue 'reconnect;
+                    }
+                }
+                result = server_result => {
+                    result?;
+                    anyhow::bail!("Control API server exited unexpectedly");
+                }
+                _ = temp_interval.tick(), if settings.mode == Mode::Temperature && last_state.unwrap_or(true) => {
+                    let Some(source) = temperature_source.as_mut() else { continue; };
+
+                    let temp = match source.read_celsius() {
+                        Ok(temp) => temp,
+                        Err(err) => {
+                            warn!("Failed to read temperature: {}", err);
+                            continue;
+                        }
+                    };
+
+                    let f = temperature::normalize(temp, settings.temperature.t_min, settings.temperature.t_max);
+                    let color = temperature::gradient_color(&settings.temperature.gradient, f);
+
+                    if last_temp_color == Some(color) {
+                        continue;
+                    }
+
+                    let client_guard = shared_client.lock().await;
+                    let client = client_guard.as_ref().unwrap();
+                    let applied = apply_temperature_color(client, &settings.temperature.devices, color).await.is_ok();
+                    drop(client_guard);
+
+                    if applied {
+                        last_temp_color = Some(color);
+                    } else {
+                        status.lock().unwrap().connected = false;
+                        warn!("Lost connection to OpenRGB while applying temperature color");
+                        continue 'reconnect;
+                    }
+                }
+                _ = shutdown.cancelled() => {
+                    info!("Service shutting down");
+                    return Ok(())
+                }
             }
         }
     }
@@ -76,22 +296,23 @@ fn service_main(_: Vec<OsString>) {
     };
 
     let rt = Runtime::new().unwrap();
+    let settings = settings();
 
-    let (shutdown_send, mut shutdown_recv) = mpsc::unbounded_channel();
+    let shutdown_token = CancellationToken::new();
     let (profile_send, mut profile_recv) = mpsc::unbounded_channel::<bool>();
+    let api_profile_send = profile_send.clone();
 
+    let event_shutdown_token = shutdown_token.clone();
     let event_handler = move |control_event| -> ServiceControlHandlerResult {
         match control_event {
             ServiceControl::PowerEvent(event) => {
                 debug!("Power event: {:?}", event);
                 match event {
                     PowerEventParam::QuerySuspend => {
-                        // Send false to disable RGB
-                        profile_send.send(false).unwrap();
+                        profile_send.send(settings.events.suspend.as_enable()).unwrap();
                     }
                     PowerEventParam::ResumeSuspend | PowerEventParam::QuerySuspendFailed => {
-                        // Send true to enable RGB
-                        profile_send.send(true).unwrap();
+                        profile_send.send(settings.events.resume.as_enable()).unwrap();
                     }
                     _ => {}
                 }
@@ -103,12 +324,10 @@ fn service_main(_: Vec<OsString>) {
 
                 match change.reason {
                     SessionChangeReason::SessionLock => {
-                        // Send false to disable RGB
-                        profile_send.send(false).unwrap();
+                        profile_send.send(settings.events.lock.as_enable()).unwrap();
                     }
                     SessionChangeReason::SessionUnlock => {
-                        // Send true to enable RGB
-                        profile_send.send(true).unwrap();
+                        profile_send.send(settings.events.unlock.as_enable()).unwrap();
                     }
                     _ => {}
                 }
@@ -117,7 +336,7 @@ fn service_main(_: Vec<OsString>) {
             }
             ServiceControl::Interrogate => ServiceControlHandlerResult::NoError,
             ServiceControl::Stop => {
-                shutdown_send.send(()).unwrap();
+                event_shutdown_token.cancel();
                 ServiceControlHandlerResult::NoError
             }
             _ => ServiceControlHandlerResult::NotImplemented,
@@ -140,7 +359,12 @@ fn service_main(_: Vec<OsString>) {
         .unwrap();
 
     let error_code = if rt
-        .block_on(profile_applier(&mut profile_recv, &mut shutdown_recv))
+        .block_on(profile_applier(
+            &mut profile_recv,
+            api_profile_send,
+            shutdown_token,
+            settings,
+        ))
         .is_err()
     {
         1
@@ -161,63 +385,175 @@ fn service_main(_: Vec<OsString>) {
         .unwrap();
 }
 
-fn init_logger() -> Handle {
-    use log::LevelFilter;
+/// Directory used for logs and configuration, under the per-user config root
+/// (`%APPDATA%` on Windows). Falls back to the directory next to the
+/// executable if `APPDATA` isn't set.
+///
+/// Note: the SCM-installed service (see `install_service`) runs as `SYSTEM`,
+/// so for that install path this resolves under the machine's hidden
+/// `systemprofile` profile rather than a real user's profile. That's a
+/// pre-existing tradeoff of running as `SYSTEM` rather than something this
+/// function should special-case; the `--user` install mode (`user_mode.rs`)
+/// runs as the logged-in user, so it gets a normal per-user path here.
+pub(crate) fn config_dir() -> std::path::PathBuf {
+    let base = std::env::var_os("APPDATA")
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(|| {
+            std::env::current_exe()
+                .unwrap()
+                .parent()
+                .unwrap()
+                .to_path_buf()
+        });
+
+    base.join(SERVICE_NAME)
+}
+
+const LOG_FILE_MAX_BYTES: u64 = 1024 * 1024;
+const LOG_FILE_ARCHIVE_COUNT: u32 = 10;
+
+/// Builds the rolling-file (plus, in debug builds, console) logging config.
+/// Kept separate from [`init_logger`] so a misconfigured log directory or
+/// roller can be handled gracefully instead of panicking during startup.
+fn build_logging_config(level: log::LevelFilter) -> anyhow::Result<log4rs::Config> {
     use log4rs::{
-        append::{console::ConsoleAppender, file::FileAppender},
+        append::{
+            console::ConsoleAppender,
+            rolling_file::{
+                policy::compound::{
+                    roll::fixed_window::FixedWindowRoller, trigger::size::SizeTrigger,
+                    CompoundPolicy,
+                },
+                RollingFileAppender,
+            },
+        },
         config::{Appender, Root},
         encode::pattern::PatternEncoder,
         Config,
     };
 
-    let stdout_appender = ConsoleAppender::builder().build();
+    let log_dir = config_dir();
+    std::fs::create_dir_all(&log_dir)?;
+
+    let log_file_path = log_dir.join("service.log");
+    // Plain-text archives: log4rs only writes gzip-compressed archives when
+    // built with its `gzip` feature, which this crate doesn't enable.
+    let archive_pattern = log_dir.join("service.{}.log");
 
-    let log_file_path = std::env::current_exe()
-        .unwrap()
-        .with_file_name("service.log");
+    let trigger = SizeTrigger::new(LOG_FILE_MAX_BYTES);
+    let roller = FixedWindowRoller::builder()
+        .build(archive_pattern.to_str().unwrap(), LOG_FILE_ARCHIVE_COUNT)?;
+    let policy = CompoundPolicy::new(Box::new(trigger), Box::new(roller));
 
-    let log_file_appender = FileAppender::builder()
+    let log_file_appender = RollingFileAppender::builder()
         .encoder(Box::new(PatternEncoder::new(
             "{date} {level} {target} - {message}{n}",
         )))
-        .build(log_file_path)
-        .unwrap();
+        .build(log_file_path, Box::new(policy))?;
 
-    let config = Config::builder()
-        .appender(Appender::builder().build("stdout", Box::new(stdout_appender)))
-        .appender(Appender::builder().build("logfile", Box::new(log_file_appender)))
-        .build(
-            Root::builder()
-                .appender("stdout")
-                .appender("logfile")
-                .build(LevelFilter::Trace),
-        )
-        .unwrap();
+    let mut config_builder =
+        Config::builder().appender(Appender::builder().build("logfile", Box::new(log_file_appender)));
+    let mut root_builder = Root::builder().appender("logfile");
+
+    if cfg!(debug_assertions) {
+        let stdout_appender = ConsoleAppender::builder().build();
+        config_builder = config_builder
+            .appender(Appender::builder().build("stdout", Box::new(stdout_appender)));
+        root_builder = root_builder.appender("stdout");
+    }
+
+    Ok(config_builder.build(root_builder.build(level))?)
+}
+
+fn init_logger() -> Handle {
+    use log::LevelFilter;
+    use log4rs::{append::console::ConsoleAppender, config::{Appender, Root}, Config};
+
+    let level = if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+
+    let config = build_logging_config(level).unwrap_or_else(|err| {
+        // The rolling file logger couldn't be set up (e.g. the config
+        // directory isn't writable) - fall back to console-only logging
+        // rather than taking the whole service down before it can log why.
+        eprintln!(
+            "Failed to initialize file logging, falling back to console only: {}",
+            err
+        );
+
+        Config::builder()
+            .appender(Appender::builder().build(
+                "stdout",
+                Box::new(ConsoleAppender::builder().build()),
+            ))
+            .build(Root::builder().appender("stdout").build(level))
+            .unwrap()
+    });
 
     log4rs::init_config(config).unwrap()
 }
 
 #[cfg(windows)]
-fn main() -> anyhow::Result<(), windows_service::Error> {
+fn main() -> anyhow::Result<()> {
     let _ = init_logger();
 
     let args = std::env::args().collect::<Vec<_>>();
     let command = args.get(1);
+    let user_mode = args.iter().any(|arg| arg == "--user");
 
     debug!("Service control executed with args: {:?}", args);
 
     if let Some(command) = command {
         match command.as_str() {
+            "install" if user_mode => {
+                user_mode::install()?;
+                info!("Registered to auto-start at logon (no administrator required)");
+                return Ok(());
+            }
             "install" => {
                 install_service()?;
                 info!("Service installed");
                 return Ok(());
             }
+            "uninstall" if user_mode => {
+                user_mode::uninstall()?;
+                info!("Removed from auto-start");
+                return Ok(());
+            }
             "uninstall" => {
                 uninstall_service()?;
                 info!("Service uninstalled");
                 return Ok(());
             }
+            "run" if user_mode => {
+                let (profile_send, mut profile_recv) = mpsc::unbounded_channel::<bool>();
+                let api_profile_send = profile_send.clone();
+                let settings = settings();
+                let shutdown_token = CancellationToken::new();
+
+                let rt = Runtime::new()?;
+                let applier_shutdown = shutdown_token.clone();
+                let applier = rt.spawn(async move {
+                    if let Err(err) =
+                        profile_applier(&mut profile_recv, api_profile_send, applier_shutdown, settings).await
+                    {
+                        warn!("Profile applier exited: {}", err);
+                    }
+                });
+
+                // Blocks the main thread pumping window messages until the
+                // window is closed by `uninstall --user`.
+                let result = user_mode::run(profile_send, settings);
+
+                shutdown_token.cancel();
+                rt.block_on(applier).ok();
+
+                result?;
+                return Ok(());
+            }
             "run" => {
                 info!("Running service (nil)");
             }
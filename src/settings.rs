@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config_dir;
+
+const SETTINGS_FILE_NAME: &str = "settings.toml";
+
+/// Which profile a given event should trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileAction {
+    Enable,
+    Disable,
+}
+
+impl ProfileAction {
+    pub fn as_enable(self) -> bool {
+        matches!(self, ProfileAction::Enable)
+    }
+}
+
+/// Maps the lock/unlock/suspend/resume events the service listens for to a
+/// profile action.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case", default)]
+pub struct EventMappings {
+    pub lock: ProfileAction,
+    pub unlock: ProfileAction,
+    pub suspend: ProfileAction,
+    pub resume: ProfileAction,
+}
+
+impl Default for EventMappings {
+    fn default() -> Self {
+        Self {
+            lock: ProfileAction::Disable,
+            unlock: ProfileAction::Enable,
+            suspend: ProfileAction::Disable,
+            resume: ProfileAction::Enable,
+        }
+    }
+}
+
+/// Host/port of the OpenRGB SDK server to connect to.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OpenRgbSettings {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for OpenRgbSettings {
+    fn default() -> Self {
+        Self {
+            host: "127.0.0.1".into(),
+            port: 6742,
+        }
+    }
+}
+
+/// The embedded HTTP control API (see `src/api.rs`), disabled by default.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ApiSettings {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for ApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 7636,
+        }
+    }
+}
+
+/// Which operating mode the service runs in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mode {
+    /// Switch OpenRGB profiles on lock/unlock/suspend/resume (the default).
+    Profile,
+    /// Continuously drive LED color from a temperature reading.
+    Temperature,
+}
+
+impl Default for Mode {
+    fn default() -> Self {
+        Mode::Profile
+    }
+}
+
+/// A color at a point (`0.0`-`1.0`) along the normalized temperature range.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+pub struct GradientStop {
+    pub position: f32,
+    pub color: (u8, u8, u8),
+}
+
+/// Configuration for [`Mode::Temperature`].
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TemperatureSettings {
+    /// Temperature (Celsius) mapped to the start of the gradient.
+    pub t_min: f32,
+    /// Temperature (Celsius) mapped to the end of the gradient.
+    pub t_max: f32,
+    pub poll_interval_secs: u64,
+    /// OpenRGB device indices to drive. Empty means all devices.
+    pub devices: Vec<u32>,
+    /// Stops bracketing the normalized temperature, sorted by `position`.
+    /// Serialized as an array of tables, so it must stay the last field.
+    pub gradient: Vec<GradientStop>,
+}
+
+impl Default for TemperatureSettings {
+    fn default() -> Self {
+        Self {
+            t_min: 30.0,
+            t_max: 80.0,
+            poll_interval_secs: 2,
+            gradient: vec![
+                GradientStop { position: 0.0, color: (0, 0, 255) },
+                GradientStop { position: 0.33, color: (0, 255, 0) },
+                GradientStop { position: 0.66, color: (255, 255, 0) },
+                GradientStop { position: 1.0, color: (255, 0, 0) },
+            ],
+            devices: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    // Scalar fields must come before the table-typed ones below, or TOML
+    // serialization fails with a "values must be emitted before tables"
+    // error once a `[section]` header has been written.
+    pub profile_enable_name: String,
+    pub profile_disable_name: String,
+    pub mode: Mode,
+    pub openrgb: OpenRgbSettings,
+    pub events: EventMappings,
+    pub api: ApiSettings,
+    pub temperature: TemperatureSettings,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            profile_enable_name: "On".into(),
+            profile_disable_name: "Off".into(),
+            mode: Mode::default(),
+            openrgb: OpenRgbSettings::default(),
+            events: EventMappings::default(),
+            api: ApiSettings::default(),
+            temperature: TemperatureSettings::default(),
+        }
+    }
+}
+
+impl Settings {
+    /// Loads settings from `settings.toml` in the config directory, writing
+    /// out the defaults if the file doesn't exist yet.
+    pub fn load() -> anyhow::Result<Self> {
+        let path = config_dir().join(SETTINGS_FILE_NAME);
+
+        if !path.exists() {
+            let settings = Settings::default();
+            settings.save(&path)?;
+            return Ok(settings);
+        }
+
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    fn save(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
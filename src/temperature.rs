@@ -0,0 +1,87 @@
+//! Gradient math and a CPU/GPU temperature source for [`settings::Mode::Temperature`](crate::settings::Mode::Temperature).
+
+use serde::Deserialize;
+use wmi::{COMLibrary, WMIConnection};
+
+use crate::settings::GradientStop;
+
+/// Normalizes `temperature` into `[0.0, 1.0]` across `[t_min, t_max]`.
+pub(crate) fn normalize(temperature: f32, t_min: f32, t_max: f32) -> f32 {
+    if t_max <= t_min {
+        return 0.0;
+    }
+
+    ((temperature - t_min) / (t_max - t_min)).clamp(0.0, 1.0)
+}
+
+/// Finds the two gradient stops bracketing `f` and linearly interpolates
+/// each color channel between them. `stops` must be sorted by `position`.
+pub(crate) fn gradient_color(stops: &[GradientStop], f: f32) -> (u8, u8, u8) {
+    let Some(first) = stops.first() else {
+        return (0, 0, 0);
+    };
+    let last = stops.last().unwrap();
+
+    if f <= first.position {
+        return first.color;
+    }
+    if f >= last.position {
+        return last.color;
+    }
+
+    let (lower, upper) = stops
+        .windows(2)
+        .map(|pair| (&pair[0], &pair[1]))
+        .find(|(lower, upper)| f >= lower.position && f <= upper.position)
+        .unwrap_or((first, last));
+
+    let span = upper.position - lower.position;
+    let t = if span > 0.0 {
+        (f - lower.position) / span
+    } else {
+        0.0
+    };
+
+    let lerp_channel = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+
+    (
+        lerp_channel(lower.color.0, upper.color.0),
+        lerp_channel(lower.color.1, upper.color.1),
+        lerp_channel(lower.color.2, upper.color.2),
+    )
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ThermalZoneTemperature {
+    current_temperature: u32,
+}
+
+/// Reads the hottest ACPI thermal zone via WMI.
+pub(crate) struct TemperatureSource {
+    conn: WMIConnection,
+}
+
+impl TemperatureSource {
+    pub(crate) fn new() -> anyhow::Result<Self> {
+        let com = COMLibrary::new()?;
+        let conn = WMIConnection::with_namespace_path("root\\WMI", com)?;
+        Ok(Self { conn })
+    }
+
+    /// Returns the hottest reported zone's temperature, in degrees Celsius.
+    pub(crate) fn read_celsius(&self) -> anyhow::Result<f32> {
+        let zones: Vec<ThermalZoneTemperature> = self
+            .conn
+            .raw_query("SELECT CurrentTemperature FROM MSAcpi_ThermalZoneTemperature")?;
+
+        let hottest = zones
+            .iter()
+            .map(|zone| zone.current_temperature)
+            .max()
+            .ok_or_else(|| anyhow::anyhow!("No ACPI thermal zones reported"))?;
+
+        // MSAcpi_ThermalZoneTemperature reports temperature in tenths of a kelvin.
+        Ok(hottest as f32 / 10.0 - 273.15)
+    }
+}
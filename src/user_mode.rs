@@ -0,0 +1,194 @@
+//! Non-admin install mode: instead of registering a Windows service, the
+//! executable is registered under the current user's `Run` key and manages
+//! its own lifecycle via a hidden message-only window, since a plain Run-key
+//! process is never handed to the service control manager.
+
+use std::ffi::OsString;
+use std::os::windows::ffi::OsStrExt;
+
+use log::{debug, info, warn};
+use tokio::sync::mpsc::UnboundedSender;
+use winreg::enums::HKEY_CURRENT_USER;
+use winreg::RegKey;
+use windows_sys::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows_sys::Win32::System::Power::{
+    RegisterSuspendResumeNotification, DEVICE_NOTIFY_WINDOW_HANDLE,
+};
+use windows_sys::Win32::System::RemoteDesktop::{
+    WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+};
+use windows_sys::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, FindWindowExW, GetMessageW,
+    PostMessageW, PostQuitMessage, RegisterClassW, TranslateMessage, CW_USEDEFAULT, HWND_MESSAGE,
+    MSG, PBT_APMRESUMESUSPEND, PBT_APMSUSPEND, WM_CLOSE, WM_DESTROY, WM_POWERBROADCAST,
+    WM_WTSSESSION_CHANGE, WNDCLASSW, WTS_SESSION_LOCK, WTS_SESSION_UNLOCK,
+};
+
+use crate::settings::Settings;
+use crate::SERVICE_NAME;
+
+const RUN_KEY_PATH: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+fn run_key() -> anyhow::Result<RegKey> {
+    let hkcu = RegKey::predef(HKEY_CURRENT_USER);
+    let (key, _) = hkcu.create_subkey(RUN_KEY_PATH)?;
+    Ok(key)
+}
+
+/// Registers the executable to auto-start at logon via `HKCU\...\Run`.
+pub(crate) fn install() -> anyhow::Result<()> {
+    let exe_path = std::env::current_exe()?;
+    let command = format!("\"{}\" run --user", exe_path.display());
+
+    run_key()?.set_value(SERVICE_NAME, &command)?;
+    Ok(())
+}
+
+/// Removes the `Run` entry and asks any currently running instance to exit.
+pub(crate) fn uninstall() -> anyhow::Result<()> {
+    run_key()?.delete_value(SERVICE_NAME)?;
+
+    if let Some(hwnd) = find_message_window() {
+        unsafe {
+            PostMessageW(hwnd, WM_CLOSE, 0, 0);
+        }
+    }
+
+    Ok(())
+}
+
+fn wide_null(s: &str) -> Vec<u16> {
+    OsString::from(s).encode_wide().chain(std::iter::once(0)).collect()
+}
+
+fn find_message_window() -> Option<HWND> {
+    let class_name = wide_null(SERVICE_NAME);
+    // Message-only windows (HWND_MESSAGE, which is what `run` creates) are
+    // never found by FindWindowW - only FindWindowExW with HWND_MESSAGE as
+    // the parent searches that window group.
+    let hwnd = unsafe {
+        FindWindowExW(
+            HWND_MESSAGE,
+            std::ptr::null_mut(),
+            class_name.as_ptr(),
+            std::ptr::null(),
+        )
+    };
+    (!hwnd.is_null()).then_some(hwnd)
+}
+
+// Stashed so `window_proc` (a plain `extern "system" fn`, which can't close
+// over state) can reach the channel and settings for the running instance.
+thread_local! {
+    static PROFILE_SEND: std::cell::RefCell<Option<UnboundedSender<bool>>> = std::cell::RefCell::new(None);
+    static SETTINGS: std::cell::RefCell<Option<&'static Settings>> = std::cell::RefCell::new(None);
+}
+
+unsafe extern "system" fn window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_WTSSESSION_CHANGE => {
+            debug!("Session change: {:?}", wparam);
+            let enable = match wparam as u32 {
+                WTS_SESSION_LOCK => SETTINGS.with(|s| s.borrow().map(|s| s.events.lock.as_enable())),
+                WTS_SESSION_UNLOCK => SETTINGS.with(|s| s.borrow().map(|s| s.events.unlock.as_enable())),
+                _ => None,
+            };
+            if let Some(enable) = enable {
+                send_profile(enable);
+            }
+            0
+        }
+        WM_POWERBROADCAST => {
+            debug!("Power event: {:?}", wparam);
+            let enable = match wparam as u32 {
+                PBT_APMSUSPEND => SETTINGS.with(|s| s.borrow().map(|s| s.events.suspend.as_enable())),
+                PBT_APMRESUMESUSPEND => SETTINGS.with(|s| s.borrow().map(|s| s.events.resume.as_enable())),
+                _ => None,
+            };
+            if let Some(enable) = enable {
+                send_profile(enable);
+            }
+            1
+        }
+        WM_CLOSE => {
+            DestroyWindow(hwnd);
+            0
+        }
+        WM_DESTROY => {
+            PostQuitMessage(0);
+            0
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+fn send_profile(enable: bool) {
+    PROFILE_SEND.with(|s| {
+        if let Some(sender) = s.borrow().as_ref() {
+            let _ = sender.send(enable);
+        }
+    });
+}
+
+/// Runs the lock/power-event loop as a plain background process: creates a
+/// hidden message-only window, subscribes to session and power
+/// notifications, and pumps messages until [`uninstall`] posts `WM_CLOSE`.
+/// Starting another instance does not close one that is already running.
+pub(crate) fn run(profile_send: UnboundedSender<bool>, settings: &'static Settings) -> anyhow::Result<()> {
+    PROFILE_SEND.with(|s| *s.borrow_mut() = Some(profile_send));
+    SETTINGS.with(|s| *s.borrow_mut() = Some(settings));
+
+    let class_name = wide_null(SERVICE_NAME);
+
+    unsafe {
+        let class = WNDCLASSW {
+            lpfnWndProc: Some(window_proc),
+            lpszClassName: class_name.as_ptr(),
+            ..std::mem::zeroed()
+        };
+        RegisterClassW(&class);
+
+        let hwnd = CreateWindowExW(
+            0,
+            class_name.as_ptr(),
+            class_name.as_ptr(),
+            0,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            HWND_MESSAGE,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null(),
+        );
+        if hwnd.is_null() {
+            anyhow::bail!("Failed to create message-only window");
+        }
+
+        if WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION) == 0 {
+            warn!("Failed to register for session notifications");
+        }
+
+        // A message-only (HWND_MESSAGE) window never receives broadcast
+        // WM_POWERBROADCAST messages - only explicitly registered windows do.
+        if RegisterSuspendResumeNotification(hwnd, DEVICE_NOTIFY_WINDOW_HANDLE) == 0 {
+            warn!("Failed to register for suspend/resume notifications");
+        }
+
+        info!("Running in user mode (no administrator privileges)");
+
+        let mut msg: MSG = std::mem::zeroed();
+        while GetMessageW(&mut msg, std::ptr::null_mut(), 0, 0) > 0 {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+    }
+
+    Ok(())
+}